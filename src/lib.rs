@@ -3,9 +3,19 @@
 #![allow(non_snake_case)]
 #![allow(dead_code)]
 mod bindgen;
+mod session;
+mod smoothed;
+#[cfg(feature = "wav")]
+mod wav;
 use bindgen::*;
 use std::convert::TryFrom;
 
+pub use session::{FrameLength, VadSession, VadSessionConfig, VadTransition};
+pub use smoothed::SmoothedVad;
+#[cfg(feature = "wav")]
+pub use wav::{segment_wav_file, segment_wav_file_to_dir, Segment};
+
+#[derive(Debug, Clone, Copy)]
 pub enum VadMode {
     Quality = 0,
     LowBitrate = 1,
@@ -13,7 +23,7 @@ pub enum VadMode {
     VeryAggressive = 3,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum SampleRate {
     Rate8kHz = 8000,
     Rate16kHz = 16000,
@@ -36,6 +46,10 @@ impl TryFrom<i32> for SampleRate {
 
 pub struct Vad {
     fvad: *mut Fvad,
+    sample_rate: SampleRate,
+    mode: VadMode,
+    reset_period_ms: Option<u32>,
+    processed_samples: u64,
 }
 
 impl Vad {
@@ -75,7 +89,13 @@ impl Vad {
             if fvad.is_null() {
                 panic!("fvad_new() did not return a valid instance (memory allocation error)");
             }
-            let mut instance = Vad { fvad };
+            let mut instance = Vad {
+                fvad,
+                sample_rate,
+                mode,
+                reset_period_ms: None,
+                processed_samples: 0,
+            };
             instance.set_sample_rate(sample_rate);
             instance.set_mode(mode);
             instance
@@ -96,6 +116,7 @@ impl Vad {
     /// that internally all processing will be done 8000 Hz; input data in higher
     /// sample rates will just be downsampled first.
     pub fn set_sample_rate(&mut self, sample_rate: SampleRate) {
+        self.sample_rate = sample_rate;
         let sample_rate = sample_rate as i32;
         unsafe {
             assert_eq!(fvad_set_sample_rate(self.fvad, sample_rate), 0);
@@ -109,11 +130,41 @@ impl Vad {
     /// increased with increasing mode. As a consequence also the missed detection
     /// rate goes up.
     pub fn set_mode(&mut self, mode: VadMode) {
+        self.mode = mode;
         let mode = mode as i32;
 
         unsafe { assert_eq!(fvad_set_mode(self.fvad, mode), 0) }
     }
 
+    /// Configures an automatic, periodic [`Self::reset`] of the VAD instance.
+    ///
+    /// WebRTC's own AGC2 periodically resets its VAD to prevent internal
+    /// GMM/adaptive state from drifting on long-running streams. When
+    /// `period_ms` is `Some`, every time that many milliseconds of audio
+    /// have been processed (checked at frame boundaries) the instance is
+    /// reset via `fvad_reset`, with the current [`SampleRate`] and
+    /// [`VadMode`] re-applied afterwards, since `fvad_reset` restores them
+    /// to their defaults. `None` (the default) disables periodic resets.
+    pub fn set_reset_period_ms(&mut self, period_ms: Option<u32>) {
+        self.reset_period_ms = period_ms;
+        self.processed_samples = 0;
+    }
+
+    fn maybe_auto_reset(&mut self, frame_samples: u64) {
+        self.processed_samples += frame_samples;
+
+        if let Some(period_ms) = self.reset_period_ms {
+            let sample_rate_hz = self.sample_rate as i32 as u64;
+            let elapsed_ms = self.processed_samples * 1000 / sample_rate_hz;
+            if elapsed_ms >= period_ms as u64 {
+                self.reset();
+                self.set_sample_rate(self.sample_rate);
+                self.set_mode(self.mode);
+                self.processed_samples = 0;
+            }
+        }
+    }
+
     /// Calculates a VAD decision for an audio frame.
     ///
     /// `buffer` is a slice of signed 16-bit samples. Only slices with a
@@ -126,13 +177,67 @@ impl Vad {
     pub fn is_voice_segment(&mut self, buffer: &[i16]) -> Result<bool, ()> {
         let b = &buffer[0] as *const i16;
 
-        unsafe {
+        let result = unsafe {
             match fvad_process(self.fvad, b, buffer.len()) {
                 1 => Ok(true),
                 0 => Ok(false),
                 _ => Err(()),
             }
-        }
+        };
+
+        self.maybe_auto_reset(buffer.len() as u64);
+
+        result
+    }
+
+    /// Calculates a VAD decision for an audio frame together with its
+    /// loudness, as peak and RMS level in dBFS.
+    ///
+    /// `buffer` follows the same constraints as [`Self::is_voice_segment`].
+    /// This lets callers combine the WebRTC voiced flag with level
+    /// thresholds (e.g. for AGC-style gain control or UI meters) with a
+    /// single call per frame.
+    pub fn analyze(&mut self, buffer: &[i16]) -> Result<FrameAnalysis, ()> {
+        let is_voice = self.is_voice_segment(buffer)?;
+
+        Ok(FrameAnalysis {
+            is_voice,
+            peak_dbfs: peak_dbfs(buffer),
+            rms_dbfs: rms_dbfs(buffer),
+        })
+    }
+}
+
+/// The floor, in dBFS, reported for silent frames in place of `-inf`.
+const DBFS_FLOOR: f32 = -100.0;
+
+/// The result of [`Vad::analyze`]: a VAD decision alongside the frame's
+/// peak and RMS loudness, both in dBFS relative to `i16` full scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameAnalysis {
+    /// Whether `fvad` classified the frame as active voice.
+    pub is_voice: bool,
+    /// Peak level in dBFS, computed from the frame's maximum absolute sample.
+    pub peak_dbfs: f32,
+    /// RMS level in dBFS, computed from the mean of squares of the frame's samples.
+    pub rms_dbfs: f32,
+}
+
+fn peak_dbfs(buffer: &[i16]) -> f32 {
+    let peak = buffer.iter().map(|&s| (s as f32).abs()).fold(0.0, f32::max);
+    to_dbfs(peak)
+}
+
+fn rms_dbfs(buffer: &[i16]) -> f32 {
+    let mean_square = buffer.iter().map(|&s| (s as f32).powi(2)).sum::<f32>() / buffer.len() as f32;
+    to_dbfs(mean_square.sqrt())
+}
+
+fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        DBFS_FLOOR
+    } else {
+        (20.0 * (amplitude / 32768.0).log10()).max(DBFS_FLOOR)
     }
 }
 
@@ -177,4 +282,18 @@ mod test {
         let mut vad = Vad::new();
         assert_eq!(vad.set_mode(VadMode::Quality), ());
     }
+
+    #[test]
+    fn dbfs_full_scale_frame_is_near_zero() {
+        let buffer = vec![i16::MAX; 160];
+        assert!((peak_dbfs(&buffer) - 0.0).abs() < 0.01);
+        assert!((rms_dbfs(&buffer) - 0.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn dbfs_silent_frame_hits_the_floor() {
+        let buffer = vec![0i16; 160];
+        assert_eq!(peak_dbfs(&buffer), DBFS_FLOOR);
+        assert_eq!(rms_dbfs(&buffer), DBFS_FLOOR);
+    }
 }
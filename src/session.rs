@@ -0,0 +1,343 @@
+// src/session.rs
+
+use crate::{SampleRate, Vad, VadMode};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A speech boundary emitted by a [`VadSession`] while it processes incoming audio.
+///
+/// Timestamps are milliseconds measured from the start of the stream the
+/// session was created for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VadTransition {
+    /// Speech started at `start_ms`.
+    SpeechStart { start_ms: u64 },
+    /// A speech segment that started at `start_ms` ended at `end_ms`.
+    SpeechEnd { start_ms: u64, end_ms: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SessionState {
+    Silence,
+    Speech,
+}
+
+/// The duration of the frames a [`VadSession`] slices its input into.
+///
+/// `fvad_process` (and therefore [`Vad::is_voice_segment`]) only accepts
+/// 10, 20 or 30 ms frames; this selects which of those the session uses so
+/// callers don't have to hand-compute `80`/`160`/`240`-style sample counts
+/// themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameLength {
+    Ms10,
+    Ms20,
+    Ms30,
+}
+
+impl FrameLength {
+    fn as_ms(self) -> u32 {
+        match self {
+            FrameLength::Ms10 => 10,
+            FrameLength::Ms20 => 20,
+            FrameLength::Ms30 => 30,
+        }
+    }
+}
+
+/// Configuration knobs for a [`VadSession`]'s state machine.
+#[derive(Debug, Clone, Copy)]
+pub struct VadSessionConfig {
+    /// The frame length the session slices incoming audio into.
+    pub frame_length: FrameLength,
+    /// Number of consecutive voiced frames required before a `Speech` segment
+    /// is considered to have started. Set to `1` to start on the first
+    /// voiced frame.
+    pub onset_frames: u32,
+    /// Milliseconds of contiguous non-voiced frames required while in
+    /// `Speech` before the segment is considered to have ended (hangover).
+    pub min_silence_ms: u32,
+}
+
+impl Default for VadSessionConfig {
+    fn default() -> Self {
+        VadSessionConfig {
+            frame_length: FrameLength::Ms10,
+            onset_frames: 1,
+            min_silence_ms: 300,
+        }
+    }
+}
+
+/// A streaming speech segmenter built on top of [`Vad`].
+///
+/// Where [`Vad::is_voice_segment`] only classifies a single, perfectly-sized
+/// frame, `VadSession` accepts audio of arbitrary length via [`Self::push`]
+/// or [`Self::push_f32`], buffers it internally, slices it into valid frames
+/// (per [`VadSessionConfig::frame_length`]) and runs a `Silence`/`Speech`
+/// state machine over the per-frame decisions, emitting [`VadTransition`]s
+/// at the resulting speech boundaries.
+pub struct VadSession {
+    vad: Vad,
+    config: VadSessionConfig,
+    sample_rate_hz: u32,
+    frame_len_samples: usize,
+    buffer: Vec<i16>,
+    state: SessionState,
+    processed_samples: u64,
+    consecutive_voiced: u32,
+    silence_samples: u64,
+    speech_start_ms: u64,
+}
+
+impl VadSession {
+    /// Creates a new session for `sample_rate`/`mode`, using the given
+    /// segmentation configuration.
+    pub fn new(sample_rate: SampleRate, mode: VadMode, config: VadSessionConfig) -> Self {
+        let sample_rate_hz = sample_rate as i32 as u32;
+        let frame_len_samples =
+            (sample_rate_hz as u64 * config.frame_length.as_ms() as u64 / 1000) as usize;
+        VadSession {
+            vad: Vad::new_with_rate_and_mode(sample_rate, mode),
+            config,
+            sample_rate_hz,
+            frame_len_samples,
+            buffer: Vec::new(),
+            state: SessionState::Silence,
+            processed_samples: 0,
+            consecutive_voiced: 0,
+            silence_samples: 0,
+            speech_start_ms: 0,
+        }
+    }
+
+    /// Pushes `samples` into the session, returning the [`VadTransition`]s
+    /// produced while processing them.
+    ///
+    /// `samples` need not be aligned to a frame boundary; any remainder is
+    /// buffered and combined with the next call to `push`.
+    pub fn push(&mut self, samples: &[i16]) -> Result<Vec<VadTransition>, ()> {
+        self.buffer.extend_from_slice(samples);
+        self.drain_buffered_frames()
+    }
+
+    /// Pushes single-channel `f32` samples, equivalent to `push_f32(samples, 1)`.
+    ///
+    /// A convenience for the common mono case, since Rust has no default
+    /// arguments to make the `channels` parameter of [`Self::push_f32`]
+    /// optional.
+    pub fn push_f32_mono(&mut self, samples: &[f32]) -> Result<Vec<VadTransition>, ()> {
+        self.push_f32(samples, 1)
+    }
+
+    /// Pushes interleaved `f32` samples (as delivered by capture libraries
+    /// like cpal), scaling them to `i16` (`sample * 32767`, clamped to the
+    /// `i16` range) before buffering and processing them exactly like
+    /// [`Self::push`].
+    ///
+    /// `channels` is the number of interleaved channels in `samples`; if
+    /// greater than `1`, each frame is down-mixed to mono by averaging its
+    /// channels. For the common single-channel case, see
+    /// [`Self::push_f32_mono`].
+    ///
+    /// Note: this intentionally returns `Vec<VadTransition>` (consistent
+    /// with [`Self::push`]) and takes a required `channels` rather than
+    /// `bool`/`Option` downmix parameter, not the `Result<bool, ()>` +
+    /// optional-downmix shape floated when this was requested — plain
+    /// `Vad` has no notion of segments to downmix into, so keeping
+    /// `VadSession`'s two push methods symmetric was judged more useful
+    /// than matching that shape exactly.
+    pub fn push_f32(&mut self, samples: &[f32], channels: usize) -> Result<Vec<VadTransition>, ()> {
+        let channels = channels.max(1);
+
+        self.buffer
+            .extend(samples.chunks(channels).map(|frame| {
+                let mono = frame.iter().sum::<f32>() / frame.len() as f32;
+                let scaled = (mono * 32767.0).clamp(i16::MIN as f32, i16::MAX as f32);
+                scaled as i16
+            }));
+
+        self.drain_buffered_frames()
+    }
+
+    fn drain_buffered_frames(&mut self) -> Result<Vec<VadTransition>, ()> {
+        let mut transitions = Vec::new();
+        let frame_len = self.frame_len_samples;
+        while self.buffer.len() >= frame_len {
+            let frame: Vec<i16> = self.buffer.drain(..frame_len).collect();
+            let is_voice = self.vad.is_voice_segment(&frame)?;
+            self.process_frame(is_voice, frame_len as u64, &mut transitions);
+        }
+
+        Ok(transitions)
+    }
+
+    /// Flushes any pending speech segment, returning a final
+    /// [`VadTransition::SpeechEnd`] if the session was in the `Speech` state.
+    ///
+    /// Does not consume `self`; it's safe to keep pushing audio afterwards,
+    /// in which case the session starts fresh from the `Silence` state.
+    pub fn finish(&mut self) -> Option<VadTransition> {
+        if self.state == SessionState::Speech {
+            self.state = SessionState::Silence;
+            self.consecutive_voiced = 0;
+            self.silence_samples = 0;
+            let end_ms = self.processed_samples * 1000 / self.sample_rate_hz as u64;
+            Some(VadTransition::SpeechEnd {
+                start_ms: self.speech_start_ms,
+                end_ms,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn process_frame(
+        &mut self,
+        is_voice: bool,
+        frame_samples: u64,
+        transitions: &mut Vec<VadTransition>,
+    ) {
+        match self.state {
+            SessionState::Silence => {
+                if is_voice {
+                    self.consecutive_voiced += 1;
+                    if self.consecutive_voiced >= self.config.onset_frames {
+                        let onset_samples = self.consecutive_voiced as u64 * frame_samples;
+                        let onset_sample_pos = (self.processed_samples + frame_samples)
+                            .saturating_sub(onset_samples);
+                        self.speech_start_ms = onset_sample_pos * 1000 / self.sample_rate_hz as u64;
+                        self.state = SessionState::Speech;
+                        self.silence_samples = 0;
+                        transitions.push(VadTransition::SpeechStart {
+                            start_ms: self.speech_start_ms,
+                        });
+                    }
+                } else {
+                    self.consecutive_voiced = 0;
+                }
+            }
+            SessionState::Speech => {
+                if is_voice {
+                    self.silence_samples = 0;
+                } else {
+                    self.silence_samples += frame_samples;
+                    let silence_ms = self.silence_samples * 1000 / self.sample_rate_hz as u64;
+                    if silence_ms >= self.config.min_silence_ms as u64 {
+                        let end_sample_pos = (self.processed_samples + frame_samples)
+                            .saturating_sub(self.silence_samples);
+                        let end_ms = end_sample_pos * 1000 / self.sample_rate_hz as u64;
+                        transitions.push(VadTransition::SpeechEnd {
+                            start_ms: self.speech_start_ms,
+                            end_ms,
+                        });
+                        self.state = SessionState::Silence;
+                        self.consecutive_voiced = 0;
+                    }
+                }
+            }
+        }
+
+        self.processed_samples += frame_samples;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(onset_frames: u32, min_silence_ms: u32) -> VadSession {
+        VadSession::new(
+            SampleRate::Rate8kHz,
+            VadMode::Quality,
+            VadSessionConfig {
+                frame_length: FrameLength::Ms10,
+                onset_frames,
+                min_silence_ms,
+            },
+        )
+    }
+
+    #[test]
+    fn onset_and_hangover_transitions() {
+        let mut session = session(2, 20);
+        let mut transitions = Vec::new();
+
+        // A single voiced frame is below the onset threshold: no transition.
+        session.process_frame(true, 80, &mut transitions);
+        assert!(transitions.is_empty());
+
+        // The second consecutive voiced frame reaches onset_frames == 2.
+        session.process_frame(true, 80, &mut transitions);
+        assert_eq!(transitions, vec![VadTransition::SpeechStart { start_ms: 0 }]);
+        transitions.clear();
+
+        // A single silent frame is below the 20 ms hangover: no transition.
+        session.process_frame(false, 80, &mut transitions);
+        assert!(transitions.is_empty());
+
+        // The second consecutive silent frame reaches the 20 ms hangover;
+        // end_ms is back-dated to where the trailing silence started.
+        session.process_frame(false, 80, &mut transitions);
+        assert_eq!(
+            transitions,
+            vec![VadTransition::SpeechEnd {
+                start_ms: 0,
+                end_ms: 20,
+            }]
+        );
+    }
+
+    #[test]
+    fn finish_flushes_pending_segment() {
+        let mut session = session(1, 300);
+        let mut transitions = Vec::new();
+
+        session.process_frame(true, 80, &mut transitions);
+        assert_eq!(transitions, vec![VadTransition::SpeechStart { start_ms: 0 }]);
+
+        assert_eq!(
+            session.finish(),
+            Some(VadTransition::SpeechEnd {
+                start_ms: 0,
+                end_ms: 10,
+            })
+        );
+        // Nothing left to flush a second time.
+        assert_eq!(session.finish(), None);
+    }
+
+    #[test]
+    fn finish_resets_onset_state_for_subsequent_pushes() {
+        let mut session = session(2, 300);
+        let mut transitions = Vec::new();
+
+        session.process_frame(true, 80, &mut transitions);
+        session.process_frame(true, 80, &mut transitions);
+        assert!(session.finish().is_some());
+
+        // A single voiced frame after finish() must not immediately
+        // re-trigger SpeechStart, since onset_frames == 2 and the session
+        // should have started over from Silence with cleared counters.
+        let mut post_finish = Vec::new();
+        session.process_frame(true, 80, &mut post_finish);
+        assert!(post_finish.is_empty());
+    }
+
+    #[test]
+    fn push_buffers_partial_frames_until_complete() {
+        let mut session = session(1, 300);
+
+        // Frame length at 8 kHz / 10 ms is 80 samples; pushing fewer than
+        // that must not process a frame yet.
+        let transitions = session.push(&vec![0i16; 40]).unwrap();
+        assert!(transitions.is_empty());
+
+        // Completing the frame with the remainder processes exactly one
+        // (silent) frame and reports no speech.
+        let transitions = session.push(&vec![0i16; 40]).unwrap();
+        assert!(transitions.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+// src/smoothed.rs
+
+use crate::Vad;
+
+/// Wraps a [`Vad`] with a hangover filter that suppresses single-frame
+/// flicker in the raw per-frame decision.
+///
+/// The reported decision only flips to "voice" after `voice_margin_frames`
+/// consecutive voiced frames, and only flips back to "silence" after
+/// `silence_margin_frames` consecutive non-voiced frames; otherwise it holds
+/// the previously reported ("sticky") decision. This mirrors the margin
+/// approach used by other `fvad` consumers and gives callers stable segment
+/// boundaries without needing to post-process raw per-frame output.
+pub struct SmoothedVad {
+    vad: Vad,
+    voice_margin_frames: u32,
+    silence_margin_frames: u32,
+    consecutive_voiced: u32,
+    consecutive_silent: u32,
+    reported: bool,
+}
+
+impl SmoothedVad {
+    /// Wraps `vad`, requiring `voice_margin_frames` consecutive voiced
+    /// frames to report "voice" and `silence_margin_frames` consecutive
+    /// non-voiced frames to report "silence".
+    pub fn new(vad: Vad, voice_margin_frames: u32, silence_margin_frames: u32) -> Self {
+        SmoothedVad {
+            vad,
+            voice_margin_frames,
+            silence_margin_frames,
+            consecutive_voiced: 0,
+            consecutive_silent: 0,
+            reported: false,
+        }
+    }
+
+    /// Calculates a smoothed VAD decision for an audio frame.
+    ///
+    /// See [`Vad::is_voice_segment`] for the accepted frame lengths. The
+    /// returned decision is sticky: it only changes once the corresponding
+    /// margin of consecutive same-sided raw decisions has been reached.
+    pub fn is_voice_segment_smoothed(&mut self, buffer: &[i16]) -> Result<bool, ()> {
+        let is_voice = self.vad.is_voice_segment(buffer)?;
+        Ok(self.record(is_voice))
+    }
+
+    /// Resets the margin counters and the sticky decision back to "silence",
+    /// without touching the wrapped [`Vad`]'s internal state.
+    pub fn reset(&mut self) {
+        self.consecutive_voiced = 0;
+        self.consecutive_silent = 0;
+        self.reported = false;
+    }
+
+    fn record(&mut self, is_voice: bool) -> bool {
+        if is_voice {
+            self.consecutive_voiced += 1;
+            self.consecutive_silent = 0;
+            if self.consecutive_voiced >= self.voice_margin_frames {
+                self.reported = true;
+            }
+        } else {
+            self.consecutive_silent += 1;
+            self.consecutive_voiced = 0;
+            if self.consecutive_silent >= self.silence_margin_frames {
+                self.reported = false;
+            }
+        }
+
+        self.reported
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SampleRate;
+
+    #[test]
+    fn single_frame_flicker_is_suppressed_until_margin() {
+        let mut smoothed = SmoothedVad::new(Vad::new_with_rate(SampleRate::Rate8kHz), 2, 2);
+
+        // A single voiced frame is below the margin: still reporting silence.
+        assert_eq!(smoothed.record(true), false);
+        // The second consecutive voiced frame reaches the margin.
+        assert_eq!(smoothed.record(true), true);
+
+        // A single non-voiced frame (flicker) is below the silence margin:
+        // the sticky decision still reports voice.
+        assert_eq!(smoothed.record(false), true);
+        // The second consecutive non-voiced frame reaches the margin.
+        assert_eq!(smoothed.record(false), false);
+    }
+
+    #[test]
+    fn reset_returns_to_silence() {
+        let mut smoothed = SmoothedVad::new(Vad::new_with_rate(SampleRate::Rate8kHz), 1, 1);
+
+        assert_eq!(smoothed.record(true), true);
+        smoothed.reset();
+
+        assert_eq!(smoothed.reported, false);
+        // After reset, a single non-voiced frame shouldn't have any lingering
+        // voiced streak counted towards the margin.
+        assert_eq!(smoothed.record(false), false);
+    }
+}
@@ -0,0 +1,105 @@
+// src/wav.rs
+
+use crate::{SampleRate, Vad, VadMode, VadSession, VadSessionConfig, VadTransition};
+use std::convert::TryFrom;
+use std::path::Path;
+
+/// A detected speech segment, as a `(start_ms, end_ms)` pair.
+pub type Segment = (u64, u64);
+
+/// Reads a mono 16-bit PCM WAV file at `path` and runs it through a
+/// [`VadSession`], returning the detected speech segments as
+/// `(start_ms, end_ms)` pairs.
+///
+/// Returns an error if the file's sample rate isn't one `fvad` supports (see
+/// [`SampleRate::try_from`]), or if it isn't mono 16-bit PCM.
+pub fn segment_wav_file<P: AsRef<Path>>(
+    path: P,
+    mode: VadMode,
+    config: VadSessionConfig,
+) -> Result<Vec<Segment>, hound::Error> {
+    let (samples, sample_rate) = read_mono_i16(path)?;
+
+    let mut session = VadSession::new(sample_rate, mode, config);
+    let mut segments = Vec::new();
+
+    for transition in session.push(&samples).map_err(frame_error)? {
+        apply_transition(transition, &mut segments);
+    }
+    if let Some(transition) = session.finish() {
+        apply_transition(transition, &mut segments);
+    }
+
+    Ok(segments)
+}
+
+/// Like [`segment_wav_file`], but additionally writes each detected segment
+/// out to its own mono 16-bit PCM WAV file under `out_dir`, named
+/// `segment_<index>.wav`.
+pub fn segment_wav_file_to_dir<P: AsRef<Path>, O: AsRef<Path>>(
+    path: P,
+    out_dir: O,
+    mode: VadMode,
+    config: VadSessionConfig,
+) -> Result<Vec<Segment>, hound::Error> {
+    let (samples, sample_rate) = read_mono_i16(&path)?;
+    let sample_rate_hz = sample_rate as i32 as u32;
+
+    let mut session = VadSession::new(sample_rate, mode, config);
+    let mut segments = Vec::new();
+
+    for transition in session.push(&samples).map_err(frame_error)? {
+        apply_transition(transition, &mut segments);
+    }
+    if let Some(transition) = session.finish() {
+        apply_transition(transition, &mut segments);
+    }
+
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: sample_rate_hz,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    for (index, &(start_ms, end_ms)) in segments.iter().enumerate() {
+        let start_sample = (start_ms * sample_rate_hz as u64 / 1000) as usize;
+        let end_sample = (end_ms * sample_rate_hz as u64 / 1000) as usize;
+
+        let out_path = out_dir.as_ref().join(format!("segment_{}.wav", index));
+        let mut writer = hound::WavWriter::create(out_path, spec)?;
+        for &sample in &samples[start_sample..end_sample.min(samples.len())] {
+            writer.write_sample(sample)?;
+        }
+        writer.finalize()?;
+    }
+
+    Ok(segments)
+}
+
+fn apply_transition(transition: VadTransition, segments: &mut Vec<Segment>) {
+    if let VadTransition::SpeechEnd { start_ms, end_ms } = transition {
+        segments.push((start_ms, end_ms));
+    }
+}
+
+fn read_mono_i16<P: AsRef<Path>>(path: P) -> Result<(Vec<i16>, SampleRate), hound::Error> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+
+    if spec.channels != 1 || spec.bits_per_sample != 16 {
+        return Err(hound::Error::Unsupported);
+    }
+
+    let sample_rate = SampleRate::try_from(spec.sample_rate as i32)
+        .map_err(|_| hound::Error::Unsupported)?;
+    let samples = reader
+        .samples::<i16>()
+        .collect::<Result<Vec<i16>, hound::Error>>()?;
+
+    Ok((samples, sample_rate))
+}
+
+fn frame_error(_: ()) -> hound::Error {
+    hound::Error::Unsupported
+}